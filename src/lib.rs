@@ -1,7 +1,10 @@
 use pathfinding::prelude::*;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
 use std::cell::Cell;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::Display;
 use std::rc::Rc;
 use std::{f64, isize};
@@ -28,7 +31,7 @@ trait Drawable {
 }
 
 #[wasm_bindgen]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Point {
     x: usize,
     y: usize,
@@ -42,22 +45,40 @@ impl Point {
     }
 }
 
-impl Point {
-    fn to(from: &Point, x: isize, y: isize) -> Point {
-        let x = from.x as isize + x;
-        let y = from.y as isize + y;
+/// Index into [`MazeCell::walls`] for the wall on each side of a cell.
+const TOP: usize = 0;
+const RIGHT: usize = 1;
+const BOTTOM: usize = 2;
+const LEFT: usize = 3;
+const SIDES: [usize; 4] = [TOP, RIGHT, BOTTOM, LEFT];
+
+/// Entry cost used when carving fresh corridors, preserving the old
+/// unweighted behavior where every path cell costs the same to enter.
+const DEFAULT_PATH_COST: u32 = 1;
+
+/// A maze cell carrying a wall flag per side (closed by default) and the
+/// cost to enter it, so the solver can route across weighted terrain.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct MazeCell {
+    walls: [bool; 4],
+    cost: u32,
+}
 
-        Point {
-            x: usize::try_from(x).expect_throw("x is out of bounds"),
-            y: usize::try_from(y).expect_throw("y is out of bounds"),
+impl Default for MazeCell {
+    fn default() -> Self {
+        MazeCell {
+            walls: [true; 4],
+            cost: DEFAULT_PATH_COST,
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum GridCell {
-    Wall,
-    Path,
+/// Selects which generator [`Maze::generate_maze`] carves the maze with.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MazeAlgorithm {
+    RecursiveBacktracker,
+    BinaryTree,
 }
 
 #[wasm_bindgen]
@@ -65,20 +86,35 @@ enum GridCell {
 pub struct Maze {
     width: usize,
     height: usize,
-    cells: Vec<GridCell>,
-    cell_size: f64,
+    cells: Vec<MazeCell>,
+    /// A clone of `cells` taken after every carve step, so generation can
+    /// be replayed frame by frame.
+    generation_history: Vec<Vec<MazeCell>>,
+    /// The solver's visited set after every expansion, so the search
+    /// wavefront can be replayed frame by frame.
+    solve_history: Vec<Vec<Point>>,
 }
 
 impl Maze {
+    /// Builds an empty, fully-walled grid. Deliberately doesn't touch the
+    /// canvas: cell size depends on the live DOM, so it's computed lazily
+    /// by [`Maze::calc_cell_size`] wherever it's needed for drawing. That
+    /// keeps maze generation (and `generate_maze` in particular) usable
+    /// headlessly, e.g. from a test.
     fn new(width: usize, height: usize) -> Self {
         Maze {
             width,
             height,
-            cells: vec![GridCell::Wall; width * height],
-            cell_size: Maze::calc_cell_size(width),
+            cells: vec![MazeCell::default(); width * height],
+            generation_history: Vec::new(),
+            solve_history: Vec::new(),
         }
     }
 
+    fn record_snapshot(&mut self) {
+        self.generation_history.push(self.cells.clone());
+    }
+
     fn calc_cell_size(width: usize) -> f64 {
         let (canvas, _context) = get_canvas();
         f64::from(canvas.width()) / width as f64
@@ -88,133 +124,266 @@ impl Maze {
         y * self.width + x
     }
 
-    fn get(&self, x: usize, y: usize) -> Option<GridCell> {
-        if x < self.width && y < self.height {
-            Some(self.cells[self.index(x, y)])
-        } else {
-            None
+    fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// The cell adjacent to `point` through `side`, or `None` at the edge
+    /// of the maze.
+    fn neighbor_at(&self, point: Point, side: usize) -> Option<Point> {
+        match side {
+            TOP if point.y > 0 => Some(Point {
+                x: point.x,
+                y: point.y - 1,
+            }),
+            RIGHT if point.x + 1 < self.width => Some(Point {
+                x: point.x + 1,
+                y: point.y,
+            }),
+            BOTTOM if point.y + 1 < self.height => Some(Point {
+                x: point.x,
+                y: point.y + 1,
+            }),
+            LEFT if point.x > 0 => Some(Point {
+                x: point.x - 1,
+                y: point.y,
+            }),
+            _ => None,
         }
     }
 
-    fn set(&mut self, x: usize, y: usize, cell: GridCell) {
-        let i = self.index(x, y);
-        self.cells[i] = cell;
+    fn neighbors(&self, point: Point) -> Vec<(usize, Point)> {
+        SIDES
+            .into_iter()
+            .filter_map(|side| self.neighbor_at(point, side).map(|next| (side, next)))
+            .collect()
     }
 
-    fn neighbors(&self, point: Point) -> Vec<Point> {
-        let mut neighbors = Vec::new();
-        let directions = [(2, 0), (-2, 0), (0, 2), (0, -2)];
+    /// Clears the wall shared by two orthogonally adjacent cells.
+    fn remove_walls(&mut self, current: Point, next: Point) {
+        let dx = next.x as isize - current.x as isize;
+        let dy = next.y as isize - current.y as isize;
+
+        let (current_side, next_side) = match (dx, dy) {
+            (0, -1) => (TOP, BOTTOM),
+            (0, 1) => (BOTTOM, TOP),
+            (-1, 0) => (LEFT, RIGHT),
+            (1, 0) => (RIGHT, LEFT),
+            _ => unreachable!("maze cells are only ever adjacent by one step"),
+        };
+
+        let current_index = self.index(current.x, current.y);
+        let next_index = self.index(next.x, next.y);
+        self.cells[current_index].walls[current_side] = false;
+        self.cells[next_index].walls[next_side] = false;
+    }
 
-        for (dx, dy) in directions.iter() {
-            if let Ok(nx) = (point.x as isize + *dx).try_into() {
-                if let Ok(ny) = (point.y as isize + *dy).try_into() {
-                    if nx < self.width && ny < self.height {
-                        neighbors.push(Point { x: nx, y: ny });
-                    }
-                }
-            }
+    fn generate_maze(
+        &mut self,
+        start: Point,
+        braidness: f64,
+        algorithm: MazeAlgorithm,
+        terrain_variance: u32,
+        rng: &mut StdRng,
+    ) {
+        self.assign_terrain_costs(terrain_variance, rng);
+        self.record_snapshot();
+
+        match algorithm {
+            MazeAlgorithm::RecursiveBacktracker => self.generate_recursive_backtracker(start, rng),
+            MazeAlgorithm::BinaryTree => self.generate_binary_tree(rng),
         }
 
-        neighbors
+        self.braid(braidness, rng);
     }
 
-    fn generate_maze(&mut self, start: Point) {
-        let mut stack = VecDeque::new();
+    /// Gives every cell a random entry cost in
+    /// `DEFAULT_PATH_COST..=DEFAULT_PATH_COST + terrain_variance`, so the
+    /// cost-weighted solvers (Dijkstra in [`path_find`], A* in
+    /// [`path_find_constrained`]) actually have varied terrain to route
+    /// across instead of every cell costing the same to enter.
+    fn assign_terrain_costs(&mut self, terrain_variance: u32, rng: &mut StdRng) {
+        if terrain_variance == 0 {
+            return;
+        }
+
+        for cell in &mut self.cells {
+            let max_cost = DEFAULT_PATH_COST.saturating_add(terrain_variance);
+            cell.cost = rng.gen_range(DEFAULT_PATH_COST..=max_cost);
+        }
+    }
+
+    /// Carves the maze with an iterative recursive backtracker: keep a
+    /// `backtrace` stack of visited cells, step to a random unvisited
+    /// neighbor and knock down the wall between them, and backtrack once a
+    /// cell has none left.
+    fn generate_recursive_backtracker(&mut self, start: Point, rng: &mut StdRng) {
+        let mut backtrace = VecDeque::new();
         let mut visited = HashSet::new();
 
-        stack.push_back(start);
+        backtrace.push_back(start);
         visited.insert(start);
 
-        while let Some(current) = stack.back().cloned() {
-            let neighbors = self.neighbors(current);
-            let unvisited_neighbors: Vec<Point> = neighbors
+        while let Some(&current) = backtrace.back() {
+            let unvisited: Vec<Point> = self
+                .neighbors(current)
                 .into_iter()
-                .filter(|&neighbor| !visited.contains(&neighbor))
+                .filter_map(|(_, neighbor)| (!visited.contains(&neighbor)).then_some(neighbor))
                 .collect();
 
-            if !unvisited_neighbors.is_empty() {
-                let next = *unvisited_neighbors.choose(&mut rand::thread_rng()).unwrap();
-                let wall = Point {
-                    x: (current.x + next.x) / 2,
-                    y: (current.y + next.y) / 2,
-                };
-
-                self.set(wall.x, wall.y, GridCell::Path);
-                self.set(next.x, next.y, GridCell::Path);
-
+            if let Some(&next) = unvisited.choose(rng) {
+                self.remove_walls(current, next);
+                self.record_snapshot();
                 visited.insert(next);
-                stack.push_back(next);
+                backtrace.push_back(next);
             } else {
-                stack.pop_back();
+                backtrace.pop_back();
             }
         }
     }
 
-    fn successors(&self, start: &Point) -> Vec<Point> {
-        [
-            Point::to(start, -1, 0),
-            Point::to(start, 1, 0),
-            Point::to(start, 0, -1),
-            Point::to(start, 0, 1),
-        ]
-        .into_iter()
-        .filter(|point| {
-            self.get(point.x, point.y)
-                .is_some_and(|c| c != GridCell::Wall)
-        })
-        .collect()
+    /// Carves the maze by visiting every cell once and knocking down its
+    /// north or west wall at random, biasing corridors toward the top-left
+    /// row and column the way the classic binary-tree algorithm does.
+    fn generate_binary_tree(&mut self, rng: &mut StdRng) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let current = Point { x, y };
+                let candidates: Vec<Point> = [TOP, LEFT]
+                    .into_iter()
+                    .filter_map(|side| self.neighbor_at(current, side))
+                    .collect();
+
+                if let Some(&next) = candidates.choose(rng) {
+                    self.remove_walls(current, next);
+                    self.record_snapshot();
+                }
+            }
+        }
+    }
+
+    /// Carves loops into an otherwise perfect maze so dead ends gain a
+    /// second way out, turning unique paths into a braided maze.
+    ///
+    /// For every dead-end cell (exactly one open wall) a different wall is
+    /// knocked down with probability `braidness`, joining it to a
+    /// neighboring passage instead of re-opening the one it came from.
+    fn braid(&mut self, braidness: f64, rng: &mut StdRng) {
+        if braidness <= 0.0 {
+            return;
+        }
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let point = Point { x, y };
+                let index = self.index(x, y);
+                let open_sides: Vec<usize> = SIDES
+                    .into_iter()
+                    .filter(|&side| !self.cells[index].walls[side])
+                    .collect();
+
+                if open_sides.len() != 1 || !rng.gen_bool(braidness) {
+                    continue;
+                }
+
+                let candidates: Vec<Point> = SIDES
+                    .into_iter()
+                    .filter(|&side| side != open_sides[0])
+                    .filter_map(|side| self.neighbor_at(point, side))
+                    .collect();
+
+                if let Some(&next) = candidates.choose(rng) {
+                    self.remove_walls(point, next);
+                    self.record_snapshot();
+                }
+            }
+        }
+    }
+
+    fn successors(&self, point: &Point) -> Vec<(Point, u32)> {
+        let index = self.index(point.x, point.y);
+        let cell = &self.cells[index];
+
+        SIDES
+            .into_iter()
+            .filter(|&side| !cell.walls[side])
+            .filter_map(|side| self.neighbor_at(*point, side))
+            .map(|neighbor| {
+                let cost = self.cells[self.index(neighbor.x, neighbor.y)].cost;
+                (neighbor, cost)
+            })
+            .collect()
     }
 }
 
-impl Drawable for Maze {
-    fn draw(&self) {
+impl Maze {
+    /// Renders an arbitrary wall-state snapshot, so both the live maze and
+    /// frames from `generation_history` can share the same drawing code.
+    fn draw_cells(&self, cells: &[MazeCell]) {
         let (_canvas, context) = get_canvas();
+        let cell_size = Maze::calc_cell_size(self.width);
+
+        context.set_fill_style(&"white".into());
+        context.fill_rect(
+            0.0,
+            0.0,
+            self.width as f64 * cell_size,
+            self.height as f64 * cell_size,
+        );
+
+        context.set_stroke_style(&"black".into());
+        context.set_line_width(2.0);
 
         for y in 0..self.height {
             for x in 0..self.width {
-                match self.get(x, y) {
-                    Some(GridCell::Wall) => {
-                        context.set_fill_style(&"black".into());
-                        context.fill_rect(
-                            x as f64 * self.cell_size,
-                            y as f64 * self.cell_size,
-                            self.cell_size,
-                            self.cell_size,
-                        );
-                    }
-                    Some(GridCell::Path) => {
-                        context.set_fill_style(&"white".into());
-                        context.fill_rect(
-                            x as f64 * self.cell_size,
-                            y as f64 * self.cell_size,
-                            self.cell_size,
-                            self.cell_size,
-                        );
-                    }
-                    None => {}
+                let cell = &cells[self.index(x, y)];
+                let x0 = x as f64 * cell_size;
+                let y0 = y as f64 * cell_size;
+                let x1 = x0 + cell_size;
+                let y1 = y0 + cell_size;
+
+                if cell.walls[TOP] {
+                    draw_line(&context, x0, y0, x1, y0);
+                }
+                if cell.walls[RIGHT] {
+                    draw_line(&context, x1, y0, x1, y1);
+                }
+                if cell.walls[BOTTOM] {
+                    draw_line(&context, x0, y1, x1, y1);
+                }
+                if cell.walls[LEFT] {
+                    draw_line(&context, x0, y0, x0, y1);
                 }
             }
         }
     }
 }
 
+impl Drawable for Maze {
+    fn draw(&self) {
+        self.draw_cells(&self.cells);
+    }
+}
+
+fn draw_line(context: &CanvasRenderingContext2d, x0: f64, y0: f64, x1: f64, y1: f64) {
+    context.begin_path();
+    context.move_to(x0, y0);
+    context.line_to(x1, y1);
+    context.stroke();
+}
+
 impl Display for Maze {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", "_".repeat(self.width * 2 - 1))?;
+
         for y in 0..self.height {
+            write!(f, "|")?;
             for x in 0..self.width {
-                match self.get(x, y) {
-                    Some(GridCell::Wall) => {
-                        write!(f, "#")?;
-                    }
-                    Some(GridCell::Path) => {
-                        write!(f, ".")?;
-                    }
-                    None => {
-                        write!(f, " ")?;
-                    }
-                }
+                let cell = &self.cells[self.index(x, y)];
+                write!(f, "{}", if cell.walls[BOTTOM] { "_" } else { " " })?;
+                write!(f, "{}", if cell.walls[RIGHT] { "|" } else { " " })?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
 
         Ok(())
@@ -302,17 +471,56 @@ fn get_canvas() -> (HtmlCanvasElement, CanvasRenderingContext2d) {
 }
 
 #[wasm_bindgen]
-pub fn draw_maze(size: usize) -> Maze {
+pub fn draw_maze(
+    size: usize,
+    braidness: f64,
+    algorithm: MazeAlgorithm,
+    terrain_variance: u32,
+) -> Maze {
+    let mut rng = StdRng::from_entropy();
+    draw_maze_with_rng(size, braidness, algorithm, terrain_variance, &mut rng)
+}
+
+/// Same as [`draw_maze`], but carves the maze from a `StdRng` seeded with
+/// `seed` so the same seed always reproduces the same maze, enabling
+/// shareable maze URLs and deterministic demos. `Maze::generate_maze` can
+/// also be exercised headlessly (see `seeded_maze_is_deterministic` below)
+/// since cell geometry is computed lazily at draw time rather than in
+/// `Maze::new`.
+#[wasm_bindgen]
+pub fn draw_maze_seeded(
+    size: usize,
+    braidness: f64,
+    algorithm: MazeAlgorithm,
+    seed: u64,
+    terrain_variance: u32,
+) -> Maze {
+    let mut rng = StdRng::seed_from_u64(seed);
+    draw_maze_with_rng(size, braidness, algorithm, terrain_variance, &mut rng)
+}
+
+fn draw_maze_with_rng(
+    size: usize,
+    braidness: f64,
+    algorithm: MazeAlgorithm,
+    terrain_variance: u32,
+    rng: &mut StdRng,
+) -> Maze {
     let mut maze = Maze::new(size, size);
-    maze.set(1, 1, GridCell::Path);
 
-    maze.generate_maze(Point { x: 1, y: 1 });
+    maze.generate_maze(
+        Point { x: 0, y: 0 },
+        braidness,
+        algorithm,
+        terrain_variance,
+        rng,
+    );
     maze.draw();
     maze
 }
 
 #[wasm_bindgen]
-pub fn add_listeners(maze: Maze) {
+pub fn add_listeners(mut maze: Maze) {
     let (canvas, context) = get_canvas();
     let start = Rc::new(Cell::new(None));
     let goal = Rc::new(Cell::new(None));
@@ -324,7 +532,7 @@ pub fn add_listeners(maze: Maze) {
             let x = (event.offset_x() as f64 / cell_size) as usize;
             let y = (event.offset_y() as f64 / cell_size) as usize;
 
-            if maze.get(x, y).is_some_and(|c| c == GridCell::Path) {
+            if maze.in_bounds(x, y) {
                 if start.get().is_none() {
                     let point = Point { x, y };
                     start.set(Some(point));
@@ -333,7 +541,7 @@ pub fn add_listeners(maze: Maze) {
                     context.fill_rect(point_x, point_y, path_size.into(), path_size.into());
                 } else if goal.get().is_none() {
                     goal.set(Some(Point { x, y }));
-                    path_find(&maze, start.get().unwrap(), goal.get().unwrap());
+                    path_find(&mut maze, start.get().unwrap(), goal.get().unwrap());
                 }
             }
         });
@@ -344,14 +552,420 @@ pub fn add_listeners(maze: Maze) {
     }
 }
 
-pub fn path_find(maze: &Maze, start: Point, goal: Point) {
-    let steps = bfs(
-        &start,
-        |n| Maze::successors(&maze, n).into_iter().collect::<Vec<_>>(),
-        |n| n == &goal,
-    )
-    .expect_throw("failed to generate path");
+/// Solves the maze with Dijkstra's algorithm, recording the solver's
+/// visited set after every expansion into `maze.solve_history` so the
+/// search wavefront can be replayed alongside the generation frames via
+/// [`step`].
+pub fn path_find(maze: &mut Maze, start: Point, goal: Point) {
+    let (steps, frontier_history) = solve_with_history(maze, start, goal);
+    maze.solve_history = frontier_history;
 
     let p = Path::new(maze.width, steps);
     p.draw();
 }
+
+fn manhattan_distance(a: &Point, b: &Point) -> u32 {
+    (a.x as isize - b.x as isize).unsigned_abs() as u32
+        + (a.y as isize - b.y as isize).unsigned_abs() as u32
+}
+
+/// Drives the search with the `pathfinding` crate's `dijkstra_reach`
+/// instead of a hand-rolled heap/`came_from` relaxation loop, so each node
+/// it yields (already deduplicated against stale heap entries) can be
+/// recorded as a frontier snapshot for the step-by-step visualizer.
+fn solve_with_history(maze: &Maze, start: Point, goal: Point) -> (Vec<Point>, Vec<Vec<Point>>) {
+    let mut parents = HashMap::new();
+    let mut frontier_history = Vec::new();
+    let mut visited = Vec::new();
+    let mut reached_goal = false;
+
+    for item in dijkstra_reach(&start, |point| maze.successors(point)) {
+        visited.push(item.node);
+        frontier_history.push(visited.clone());
+
+        if let Some(parent) = item.parent {
+            parents.insert(item.node, parent);
+        }
+
+        if item.node == goal {
+            reached_goal = true;
+            break;
+        }
+    }
+
+    if !reached_goal {
+        wasm_bindgen::throw_str("failed to generate path");
+    }
+
+    let mut path = vec![goal];
+    while let Some(&previous) = parents.get(path.last().unwrap()) {
+        path.push(previous);
+    }
+    path.reverse();
+
+    (path, frontier_history)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    fn wall_side(self) -> usize {
+        match self {
+            Direction::Up => TOP,
+            Direction::Down => BOTTOM,
+            Direction::Left => LEFT,
+            Direction::Right => RIGHT,
+        }
+    }
+}
+
+/// Search state for the run-length constrained solver: the current
+/// position, the direction of the last move (`None` at the start), and how
+/// many consecutive cells have been crossed in that direction.
+type RunState = (Point, Option<Direction>, u32);
+
+fn run_successors(
+    maze: &Maze,
+    state: &RunState,
+    min_run: u32,
+    max_run: u32,
+) -> Vec<(RunState, u32)> {
+    let &(point, last_direction, run_length) = state;
+    let index = maze.index(point.x, point.y);
+    let cell = &maze.cells[index];
+
+    Direction::ALL
+        .into_iter()
+        .filter(|&direction| match last_direction {
+            Some(last) if last == direction => run_length < max_run,
+            Some(_) => run_length >= min_run,
+            None => true,
+        })
+        .filter(|&direction| !cell.walls[direction.wall_side()])
+        .filter_map(|direction| {
+            maze.neighbor_at(point, direction.wall_side()).map(|next| {
+                let next_cost = maze.cells[maze.index(next.x, next.y)].cost;
+                let next_run = if Some(direction) == last_direction {
+                    run_length + 1
+                } else {
+                    1
+                };
+                ((next, Some(direction), next_run), next_cost)
+            })
+        })
+        .collect()
+}
+
+/// Pure run-length constrained search, split out from
+/// [`path_find_constrained`] so it can be unit tested without a canvas.
+fn solve_run_constrained(
+    maze: &Maze,
+    start: Point,
+    goal: Point,
+    min_run: u32,
+    max_run: u32,
+) -> Vec<Point> {
+    let start_state: RunState = (start, None, 0);
+
+    let (steps, _cost) = astar(
+        &start_state,
+        |state| run_successors(maze, state, min_run, max_run),
+        |state| manhattan_distance(&state.0, &goal),
+        |state| state.0 == goal && state.2 >= min_run,
+    )
+    .expect_throw("failed to generate constrained path");
+
+    steps.into_iter().map(|(point, _, _)| point).collect()
+}
+
+/// Solves the maze like [`path_find`] but forbids moving more than
+/// `max_run` cells in a straight line and requires at least `min_run`
+/// cells before turning (and before stopping at the goal), mimicking a
+/// vehicle that can't pivot on a dime.
+pub fn path_find_constrained(
+    maze: &Maze,
+    start: Point,
+    goal: Point,
+    min_run: u32,
+    max_run: u32,
+) -> Vec<Point> {
+    let points = solve_run_constrained(maze, start, goal, min_run, max_run);
+    let p = Path::new(maze.width, points.clone());
+    p.draw();
+    points
+}
+
+#[wasm_bindgen]
+pub fn solve_constrained(maze: &Maze, start: Point, goal: Point, min_run: u32, max_run: u32) {
+    path_find_constrained(maze, start, goal, min_run, max_run);
+}
+
+/// Number of generation frames recorded for `maze`, i.e. how far `step` can
+/// be driven before it starts replaying the solve history instead.
+#[wasm_bindgen]
+pub fn generation_frames(maze: &Maze) -> usize {
+    maze.generation_history.len()
+}
+
+/// Number of solve frames recorded for `maze` after a [`path_find`] call.
+#[wasm_bindgen]
+pub fn solve_frames(maze: &Maze) -> usize {
+    maze.solve_history.len()
+}
+
+/// Draws a single frame of the `index`-th step: a generation snapshot
+/// while `index` is within `generation_history`, then the finished maze
+/// with the solver's frontier at that point overlaid. A JS
+/// `requestAnimationFrame` loop can call this with increasing `index` to
+/// play back maze construction followed by the search wavefront.
+#[wasm_bindgen]
+pub fn step(maze: &Maze, index: usize) {
+    if let Some(cells) = maze.generation_history.get(index) {
+        maze.draw_cells(cells);
+        return;
+    }
+
+    maze.draw();
+
+    let solve_index = index - maze.generation_history.len();
+    if let Some(frontier) = maze.solve_history.get(solve_index) {
+        draw_frontier(maze, frontier);
+    }
+}
+
+fn draw_frontier(maze: &Maze, frontier: &[Point]) {
+    let (_canvas, context) = get_canvas();
+    context.set_fill_style(&"lightskyblue".into());
+
+    let cell_size = Maze::calc_cell_size(maze.width);
+    let inset = cell_size * 0.1;
+    let size = cell_size * 0.8;
+
+    for point in frontier {
+        let x = point.x as f64 * cell_size + inset;
+        let y = point.y as f64 * cell_size + inset;
+        context.fill_rect(x, y, size, size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `Maze::new`/`generate_maze` never touch the canvas, so this runs
+    /// headlessly: the same seed must carve identical walls and terrain
+    /// costs both times.
+    #[test]
+    fn seeded_maze_is_deterministic() {
+        let start = Point { x: 0, y: 0 };
+
+        let mut rng_a = StdRng::seed_from_u64(42);
+        let mut maze_a = Maze::new(5, 5);
+        maze_a.generate_maze(
+            start,
+            0.3,
+            MazeAlgorithm::RecursiveBacktracker,
+            4,
+            &mut rng_a,
+        );
+
+        let mut rng_b = StdRng::seed_from_u64(42);
+        let mut maze_b = Maze::new(5, 5);
+        maze_b.generate_maze(
+            start,
+            0.3,
+            MazeAlgorithm::RecursiveBacktracker,
+            4,
+            &mut rng_b,
+        );
+
+        assert_eq!(maze_a.cells, maze_b.cells);
+    }
+
+    /// Braiding must never clear a boundary wall, and the maze must stay
+    /// fully connected: every cell reachable from `start` through open
+    /// walls.
+    #[test]
+    fn braiding_preserves_borders_and_connectivity() {
+        let start = Point { x: 0, y: 0 };
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut maze = Maze::new(5, 5);
+        maze.generate_maze(start, 1.0, MazeAlgorithm::RecursiveBacktracker, 0, &mut rng);
+
+        for y in 0..maze.height {
+            for x in 0..maze.width {
+                let cell = &maze.cells[maze.index(x, y)];
+                if x == 0 {
+                    assert!(cell.walls[LEFT], "left border wall removed at ({x},{y})");
+                }
+                if x == maze.width - 1 {
+                    assert!(cell.walls[RIGHT], "right border wall removed at ({x},{y})");
+                }
+                if y == 0 {
+                    assert!(cell.walls[TOP], "top border wall removed at ({x},{y})");
+                }
+                if y == maze.height - 1 {
+                    assert!(
+                        cell.walls[BOTTOM],
+                        "bottom border wall removed at ({x},{y})"
+                    );
+                }
+            }
+        }
+
+        let mut reachable = HashSet::new();
+        let mut stack = vec![start];
+        reachable.insert(start);
+        while let Some(point) = stack.pop() {
+            for (neighbor, _cost) in maze.successors(&point) {
+                if reachable.insert(neighbor) {
+                    stack.push(neighbor);
+                }
+            }
+        }
+        assert_eq!(
+            reachable.len(),
+            maze.width * maze.height,
+            "maze is not fully connected after braiding"
+        );
+    }
+
+    /// Builds a small fully-open grid where the direct route crosses one
+    /// expensive cell, and checks the solver prefers the cheaper, longer
+    /// route instead of the shorter one.
+    #[test]
+    fn weighted_search_prefers_cheaper_route() {
+        let mut maze = Maze::new(3, 2);
+        let edges = [
+            (Point { x: 0, y: 0 }, Point { x: 1, y: 0 }),
+            (Point { x: 1, y: 0 }, Point { x: 2, y: 0 }),
+            (Point { x: 0, y: 1 }, Point { x: 1, y: 1 }),
+            (Point { x: 1, y: 1 }, Point { x: 2, y: 1 }),
+            (Point { x: 0, y: 0 }, Point { x: 0, y: 1 }),
+            (Point { x: 1, y: 0 }, Point { x: 1, y: 1 }),
+            (Point { x: 2, y: 0 }, Point { x: 2, y: 1 }),
+        ];
+        for (a, b) in edges {
+            maze.remove_walls(a, b);
+        }
+        let expensive = maze.index(1, 0);
+        maze.cells[expensive].cost = 100;
+        let start = Point { x: 0, y: 0 };
+        let goal = Point { x: 2, y: 0 };
+        let (path, _history) = solve_with_history(&maze, start, goal);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert!(
+            !path.contains(&Point { x: 1, y: 0 }),
+            "minimum-cost path should avoid the expensive cell, got {path:?}"
+        );
+        assert_eq!(
+            path.len(),
+            5,
+            "the cheaper route is longer in hop count than the direct route"
+        );
+    }
+
+    /// A direction helper for [`run_length_limit_forces_a_longer_detour`]:
+    /// mirrors the `(dx, dy)` matching `Maze::remove_walls` uses, but in
+    /// terms of [`Direction`] instead of wall sides.
+    fn direction_between(a: Point, b: Point) -> Direction {
+        match (b.x as isize - a.x as isize, b.y as isize - a.y as isize) {
+            (1, 0) => Direction::Right,
+            (-1, 0) => Direction::Left,
+            (0, 1) => Direction::Down,
+            (0, -1) => Direction::Up,
+            _ => unreachable!("steps are only ever one cell apart"),
+        }
+    }
+
+    /// Builds a small fully-open grid where the unconstrained shortest path
+    /// crosses straight over 3 cells, and checks that capping `max_run` at
+    /// 1 forces a longer, zigzagging detour that never takes two
+    /// consecutive steps in the same direction.
+    #[test]
+    fn run_length_limit_forces_a_longer_detour() {
+        let mut maze = Maze::new(4, 2);
+        let edges = [
+            (Point { x: 0, y: 0 }, Point { x: 1, y: 0 }),
+            (Point { x: 1, y: 0 }, Point { x: 2, y: 0 }),
+            (Point { x: 2, y: 0 }, Point { x: 3, y: 0 }),
+            (Point { x: 0, y: 1 }, Point { x: 1, y: 1 }),
+            (Point { x: 1, y: 1 }, Point { x: 2, y: 1 }),
+            (Point { x: 2, y: 1 }, Point { x: 3, y: 1 }),
+            (Point { x: 0, y: 0 }, Point { x: 0, y: 1 }),
+            (Point { x: 1, y: 0 }, Point { x: 1, y: 1 }),
+            (Point { x: 2, y: 0 }, Point { x: 2, y: 1 }),
+            (Point { x: 3, y: 0 }, Point { x: 3, y: 1 }),
+        ];
+        for (a, b) in edges {
+            maze.remove_walls(a, b);
+        }
+        let start = Point { x: 0, y: 0 };
+        let goal = Point { x: 3, y: 0 };
+
+        let unconstrained = solve_run_constrained(&maze, start, goal, 0, u32::MAX);
+        assert_eq!(
+            unconstrained.len(),
+            4,
+            "unconstrained route is the direct one"
+        );
+
+        let constrained = solve_run_constrained(&maze, start, goal, 0, 1);
+        assert_eq!(constrained.first(), Some(&start));
+        assert_eq!(constrained.last(), Some(&goal));
+        assert!(
+            constrained.len() > unconstrained.len(),
+            "run-length limit should force a longer detour"
+        );
+
+        let mut last_direction = None;
+        let mut run_length = 0;
+        for pair in constrained.windows(2) {
+            let direction = direction_between(pair[0], pair[1]);
+            run_length = if Some(direction) == last_direction {
+                run_length + 1
+            } else {
+                1
+            };
+            assert!(run_length <= 1, "exceeded max_run of 1");
+            last_direction = Some(direction);
+        }
+    }
+
+    /// `solve_with_history`'s frontier snapshots must never repeat a point
+    /// (each is the running visited set after one more expansion), and the
+    /// final frame's last point must be the goal.
+    #[test]
+    fn solve_history_never_repeats_a_point() {
+        let start = Point { x: 0, y: 0 };
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut maze = Maze::new(6, 6);
+        maze.generate_maze(start, 1.0, MazeAlgorithm::RecursiveBacktracker, 0, &mut rng);
+        let goal = Point { x: 5, y: 5 };
+
+        let (_path, history) = solve_with_history(&maze, start, goal);
+        let last_frame = history.last().expect("solve_history should not be empty");
+        assert_eq!(last_frame.last(), Some(&goal));
+
+        let unique: HashSet<&Point> = last_frame.iter().collect();
+        assert_eq!(
+            unique.len(),
+            last_frame.len(),
+            "solve_history's final frontier repeats a point: {last_frame:?}"
+        );
+    }
+}